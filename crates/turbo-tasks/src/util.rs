@@ -8,6 +8,7 @@ use std::{
 };
 
 use anyhow::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub use super::{id_factory::IdFactory, no_move_vec::NoMoveVec, once_map::*};
 
@@ -47,6 +48,23 @@ impl From<Error> for SharedError {
     }
 }
 
+/// Errors are serialized as their message, with the context chain flattened
+/// into it (the `{:#}` form, e.g. `outer context: root cause`). Deserializing
+/// yields an error holding only that flattened message and no source, so its
+/// plain `Display` output matches the alternate form of the original error.
+impl Serialize for SharedError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:#}", self.inner))
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let message = String::deserialize(deserializer)?;
+        Ok(Self::new(Error::msg(message)))
+    }
+}
+
 pub struct FormatDuration(pub Duration);
 
 impl Display for FormatDuration {
@@ -176,3 +194,26 @@ impl<T: ?Sized + Debug + 'static> Debug for StaticOrArc<T> {
         (**self).fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::SharedError;
+
+    #[test]
+    fn serde_round_trip() {
+        let err = SharedError::new(anyhow!("root cause").context("outer context"));
+        let result: Result<u32, SharedError> = Err(err);
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, r#"{"Err":"outer context: root cause"}"#);
+        let result: Result<u32, SharedError> = serde_json::from_str(&json).unwrap();
+        let err = result.unwrap_err();
+        assert_eq!(err.to_string(), "outer context: root cause");
+        assert_eq!(format!("{err:#}"), "outer context: root cause");
+
+        let json = serde_json::to_string(&Ok::<u32, SharedError>(42)).unwrap();
+        let result: Result<u32, SharedError> = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.unwrap(), 42);
+    }
+}
@@ -0,0 +1,788 @@
+use std::{
+    ascii,
+    borrow::Cow,
+    cmp::Ordering,
+    fmt,
+    io::{self, Cursor},
+    ops::RangeBounds,
+    str::Utf8Error,
+    string::FromUtf8Error,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bytes::{Buf, BufMut, Bytes as RawBytes, BytesMut};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use turbo_tasks::util::FormatBytes;
+use turbo_tasks_hash::{DeterministicHash, DeterministicHasher};
+
+/// A wrapper around [bytes::Bytes] that implements [Serialize] and
+/// [Deserialize].
+///
+/// Bytes are ordered lexicographically by content, so a prefix sorts before
+/// every longer value that starts with it.
+///
+/// [Display] output is capped at 1024 characters, and so is `to_string()`.
+/// Don't use it to convert bytes to text; use [Bytes::to_str],
+/// [Bytes::to_string_lossy] or [Bytes::display_full] instead, which keep the
+/// whole content.
+///
+/// [Display]: fmt::Display
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bytes(RawBytes);
+
+/// The maximum number of bytes shown by the [Debug] implementation of
+/// [Bytes]. The alternate flag (`{:#?}`) uses the larger limit.
+const DEBUG_PREVIEW_LEN: usize = 32;
+const DEBUG_PREVIEW_LEN_ALTERNATE: usize = 256;
+
+/// The default maximum number of characters shown by the [Display]
+/// implementation of [Bytes]. A precision (`{:.80}`) overrides it.
+///
+/// [Display]: fmt::Display
+const DISPLAY_LEN: usize = 1024;
+
+impl Bytes {
+    /// Creates bytes pointing directly at static data, without allocating or
+    /// copying.
+    pub const fn from_static(bytes: &'static [u8]) -> Bytes {
+        Bytes(RawBytes::from_static(bytes))
+    }
+
+    /// Returns the bytes as a slice.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+
+    /// Returns a [Read]/[BufRead]/[Seek] cursor over the bytes. The cursor
+    /// holds a cheap clone, so the bytes are not copied.
+    ///
+    /// [Read]: std::io::Read
+    /// [BufRead]: std::io::BufRead
+    /// [Seek]: std::io::Seek
+    pub fn reader(&self) -> Cursor<Bytes> {
+        Cursor::new(self.clone())
+    }
+
+    /// Returns a [Display] adapter that prints the whole content, without the
+    /// length cap of [Bytes]' own [Display] implementation.
+    ///
+    /// [Display]: fmt::Display
+    pub fn display_full(&self) -> DisplayFull<'_> {
+        DisplayFull(self)
+    }
+
+    /// Compares the content lexicographically against a byte slice. This is
+    /// the same ordering as [Ord] on [Bytes].
+    pub fn cmp_content(&self, other: &[u8]) -> Ordering {
+        self.as_bytes().cmp(other)
+    }
+
+    /// Returns a sub-range of the bytes. This shares the underlying buffer
+    /// instead of copying.
+    ///
+    /// Panics if the range is out of bounds, like [bytes::Bytes::slice].
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Bytes {
+        Bytes(self.0.slice(range))
+    }
+
+    /// Splits the bytes in two at `at`. Afterwards `self` contains `[0, at)`
+    /// and the returned value contains `[at, len)`. Both share the underlying
+    /// buffer.
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Bytes {
+        Bytes(self.0.split_off(at))
+    }
+
+    /// Splits the bytes in two at `at`. Afterwards `self` contains `[at, len)`
+    /// and the returned value contains `[0, at)`. Both share the underlying
+    /// buffer.
+    ///
+    /// Panics if `at > len`.
+    pub fn split_to(&mut self, at: usize) -> Bytes {
+        Bytes(self.0.split_to(at))
+    }
+
+    /// Drops the first `cnt` bytes.
+    ///
+    /// Panics if `cnt > len`, like [Buf::advance].
+    pub fn advance(&mut self, cnt: usize) {
+        self.0.advance(cnt)
+    }
+
+    /// Takes the first `len` bytes off the front. The returned value shares
+    /// the underlying buffer.
+    ///
+    /// Panics if `len` exceeds the remaining bytes, like [Buf::copy_to_bytes].
+    pub fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        Bytes(self.0.copy_to_bytes(len))
+    }
+
+    /// Takes a `u8` off the front.
+    ///
+    /// Panics if the bytes are empty, like [Buf::get_u8].
+    pub fn get_u8(&mut self) -> u8 {
+        self.0.get_u8()
+    }
+
+    /// Takes a big-endian `u16` off the front.
+    ///
+    /// Panics if fewer than 2 bytes remain, like [Buf::get_u16].
+    pub fn get_u16(&mut self) -> u16 {
+        self.0.get_u16()
+    }
+
+    /// Takes a big-endian `u32` off the front.
+    ///
+    /// Panics if fewer than 4 bytes remain, like [Buf::get_u32].
+    pub fn get_u32(&mut self) -> u32 {
+        self.0.get_u32()
+    }
+
+    /// Takes a big-endian `u64` off the front.
+    ///
+    /// Panics if fewer than 8 bytes remain, like [Buf::get_u64].
+    pub fn get_u64(&mut self) -> u64 {
+        self.0.get_u64()
+    }
+
+    /// Shortens the bytes to `len`, keeping the front. Does nothing if `len`
+    /// is greater than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+
+    /// Joins several bytes into one buffer with a single allocation. Empty
+    /// inputs are skipped, and a single remaining input is returned as is,
+    /// without copying.
+    pub fn concat<I: IntoIterator<Item = Bytes>>(iter: I) -> Bytes {
+        let mut parts: Vec<Bytes> = iter.into_iter().filter(|part| !part.0.is_empty()).collect();
+        if parts.len() == 1 {
+            return parts.pop().unwrap();
+        }
+        let len = parts.iter().map(|part| part.0.len()).sum();
+        let mut joined = Vec::with_capacity(len);
+        for part in &parts {
+            joined.extend_from_slice(part.as_bytes());
+        }
+        Bytes(joined.into())
+    }
+
+    /// Returns the bytes as a UTF-8 string slice.
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(self.as_bytes())
+    }
+
+    /// Converts the bytes into a UTF-8 string. The buffer is reused when this
+    /// is its only reference, otherwise it is copied.
+    pub fn into_string(self) -> Result<String, FromUtf8Error> {
+        String::from_utf8(self.0.into())
+    }
+
+    /// Returns the bytes as a string, replacing invalid UTF-8 sequences with
+    /// U+FFFD.
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.as_bytes())
+    }
+}
+
+/// Prints a bounded, escaped preview of the content and the human-readable
+/// total size: `b"GET /_next/static/chunks/main.js"… (1.22KiB)`.
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = if f.alternate() {
+            DEBUG_PREVIEW_LEN_ALTERNATE
+        } else {
+            DEBUG_PREVIEW_LEN
+        };
+        let bytes = self.as_bytes();
+        let preview = &bytes[..bytes.len().min(limit)];
+        f.write_str("b\"")?;
+        for &byte in preview {
+            // `'` needs no escaping inside a byte string literal.
+            if byte == b'\'' {
+                f.write_str("'")?;
+            } else {
+                fmt::Display::fmt(&ascii::escape_default(byte), f)?;
+            }
+        }
+        f.write_str("\"")?;
+        if preview.len() < bytes.len() {
+            f.write_str("…")?;
+        }
+        write!(f, " ({})", FormatBytes(bytes.len()))
+    }
+}
+
+/// Prints the content as UTF-8 text, replacing invalid sequences with U+FFFD.
+/// The output is capped at 1024 characters (or the formatter's precision) and
+/// ends with `…` when truncated. See [Bytes::display_full] for uncapped output.
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = f.precision().unwrap_or(DISPLAY_LEN);
+        // A char takes at most 4 bytes, so this prefix still holds more than
+        // `limit` chars whenever the full content does.
+        let bytes = self.as_bytes();
+        let prefix = &bytes[..bytes.len().min(limit.saturating_add(1).saturating_mul(4))];
+        let text = String::from_utf8_lossy(prefix);
+        match text.char_indices().nth(limit) {
+            Some((end, _)) => write!(f, "{}…", &text[..end]),
+            None => f.write_str(&text),
+        }
+    }
+}
+
+/// Prints the full content of a [Bytes] as lossy UTF-8 text. See
+/// [Bytes::display_full].
+pub struct DisplayFull<'a>(&'a Bytes);
+
+impl fmt::Display for DisplayFull<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_string_lossy())
+    }
+}
+
+impl DeterministicHash for Bytes {
+    fn deterministic_hash<H: DeterministicHasher>(&self, state: &mut H) {
+        self.as_bytes().deterministic_hash(state);
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl From<RawBytes> for Bytes {
+    fn from(bytes: RawBytes) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes.into())
+    }
+}
+
+impl From<String> for Bytes {
+    fn from(string: String) -> Self {
+        Self(string.into())
+    }
+}
+
+/// See [Bytes::into_string].
+impl TryFrom<Bytes> for String {
+    type Error = FromUtf8Error;
+
+    fn try_from(bytes: Bytes) -> Result<Self, Self::Error> {
+        bytes.into_string()
+    }
+}
+
+impl From<&'static str> for Bytes {
+    fn from(string: &'static str) -> Self {
+        Self::from_static(string.as_bytes())
+    }
+}
+
+impl From<&'static [u8]> for Bytes {
+    fn from(bytes: &'static [u8]) -> Self {
+        Self::from_static(bytes)
+    }
+}
+
+/// Human-readable formats (e.g. JSON) encode the bytes as a base64 string,
+/// binary formats write them as a raw byte blob.
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&BASE64.encode(self.0.as_ref()))
+        } else {
+            serializer.serialize_bytes(self.0.as_ref())
+        }
+    }
+}
+
+/// Accepts base64 strings and raw byte blobs, as well as sequences of
+/// integers, which older versions serialized bytes as.
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BytesVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a base64 string or a byte array")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let bytes = BASE64
+            .decode(v)
+            .map_err(|err| E::custom(format!("invalid base64 bytes: {err}")))?;
+        Ok(Bytes::from(bytes))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Bytes(RawBytes::copy_from_slice(v)))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Bytes::from(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Bytes::from(bytes))
+    }
+}
+
+/// A growable buffer for building a [Bytes] incrementally, e.g. from string
+/// formatting or encoder output.
+#[derive(Default, Debug)]
+pub struct BytesBuilder(BytesMut);
+
+impl BytesBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(BytesMut::with_capacity(capacity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes)
+    }
+
+    pub fn push_str(&mut self, string: &str) {
+        self.0.extend_from_slice(string.as_bytes())
+    }
+
+    pub fn put_u8(&mut self, n: u8) {
+        self.0.put_u8(n)
+    }
+
+    /// Writes a big-endian `u32`.
+    pub fn put_u32(&mut self, n: u32) {
+        self.0.put_u32(n)
+    }
+
+    /// Writes a big-endian `u64`.
+    pub fn put_u64(&mut self, n: u64) {
+        self.0.put_u64(n)
+    }
+
+    /// Takes the bytes written so far as an immutable [Bytes]. The builder is
+    /// left empty and can be reused.
+    pub fn freeze(&mut self) -> Bytes {
+        Bytes(self.0.split().freeze())
+    }
+}
+
+impl fmt::Write for BytesBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl io::Write for BytesBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Appends the parts in order. Use [Bytes::concat] to join parts that are
+/// all known up front.
+impl Extend<Bytes> for BytesBuilder {
+    fn extend<I: IntoIterator<Item = Bytes>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|bytes| bytes.0))
+    }
+}
+
+impl<'a> Extend<&'a [u8]> for BytesBuilder {
+    fn extend<I: IntoIterator<Item = &'a [u8]>>(&mut self, iter: I) {
+        for bytes in iter {
+            self.extend_from_slice(bytes);
+        }
+    }
+}
+
+impl Extend<u8> for BytesBuilder {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cmp::Ordering,
+        collections::hash_map::DefaultHasher,
+        fmt::Write,
+        hash::{Hash, Hasher},
+        io::{self, BufRead, Read, Seek, SeekFrom},
+        ptr,
+    };
+
+    use turbo_tasks_hash::hash_xxh3_hash64;
+
+    use super::{Bytes, BytesBuilder};
+
+    fn std_hash(bytes: &Bytes) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn ord() {
+        let abc = Bytes::from_static(b"abc");
+        let abcd = Bytes::from_static(b"abcd");
+        let empty = Bytes::from_static(b"");
+        assert!(empty < abc);
+        assert!(abc < abcd);
+        assert!(abcd < Bytes::from_static(b"abd"));
+        let heap_abc = Bytes::from("abc".to_string());
+        assert_eq!(abc.cmp(&heap_abc), Ordering::Equal);
+        assert_eq!(abc, heap_abc);
+        assert_eq!(std_hash(&abc), std_hash(&heap_abc));
+        assert_eq!(abc.cmp_content(b"abcd"), Ordering::Less);
+        assert_eq!(empty.cmp_content(b""), Ordering::Equal);
+    }
+
+    #[test]
+    fn reader() {
+        let bytes = Bytes::from_static(b"\x00\x00\x00\x05hello world");
+        let mut reader = bytes.reader();
+        let mut len = [0; 4];
+        reader.read_exact(&mut len).unwrap();
+        let mut record = vec![0; u32::from_be_bytes(len) as usize];
+        reader.read_exact(&mut record).unwrap();
+        assert_eq!(record, b"hello");
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, " world");
+
+        reader.seek(SeekFrom::Start(4)).unwrap();
+        assert_eq!(reader.fill_buf().unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn consume() {
+        let mut bytes = Bytes::from_static(b"\x01\x00\x02\x00\x00\x00\x03abcrest");
+        let backing = bytes.as_bytes().as_ptr();
+        assert_eq!(bytes.get_u8(), 1);
+        assert_eq!(bytes.get_u16(), 2);
+        let len = bytes.get_u32() as usize;
+        let payload = bytes.copy_to_bytes(len);
+        assert_eq!(payload, Bytes::from_static(b"abc"));
+        assert_eq!(payload.as_bytes().as_ptr(), backing.wrapping_add(7));
+        assert_eq!(bytes, Bytes::from_static(b"rest"));
+        bytes.advance(4);
+        assert!(bytes.as_bytes().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot advance past `remaining`")]
+    fn advance_past_end() {
+        Bytes::from_static(b"abc").advance(4);
+    }
+
+    #[test]
+    fn slice() {
+        let bytes = Bytes::from(b"hello world".to_vec());
+        let backing = bytes.as_bytes().as_ptr();
+        let world = bytes.slice(6..);
+        assert_eq!(world, Bytes::from_static(b"world"));
+        assert_eq!(world.as_bytes().as_ptr(), backing.wrapping_add(6));
+        let or = world.slice(1..3);
+        assert_eq!(or, Bytes::from_static(b"or"));
+        assert_eq!(or.as_bytes().as_ptr(), backing.wrapping_add(7));
+
+        let full = bytes.slice(..);
+        assert_eq!(full, bytes);
+        assert_eq!(full.as_bytes().as_ptr(), backing);
+
+        assert!(bytes.slice(3..3).as_bytes().is_empty());
+        assert!(bytes.slice(11..).as_bytes().is_empty());
+    }
+
+    #[test]
+    fn split() {
+        let mut bytes = Bytes::from(b"hello world".to_vec());
+        let backing = bytes.as_bytes().as_ptr();
+        let head = bytes.split_to(6);
+        assert_eq!(head, Bytes::from_static(b"hello "));
+        assert_eq!(head.as_bytes().as_ptr(), backing);
+        assert_eq!(bytes.as_bytes().as_ptr(), backing.wrapping_add(6));
+
+        let tail = bytes.split_off(3);
+        assert_eq!(bytes, Bytes::from_static(b"wor"));
+        assert_eq!(tail, Bytes::from_static(b"ld"));
+        assert_eq!(tail.as_bytes().as_ptr(), backing.wrapping_add(9));
+        assert!(bytes.split_off(3).as_bytes().is_empty());
+        assert!(bytes.split_to(0).as_bytes().is_empty());
+
+        bytes.truncate(10);
+        assert_eq!(bytes, Bytes::from_static(b"wor"));
+        bytes.truncate(1);
+        assert_eq!(bytes, Bytes::from_static(b"w"));
+    }
+
+    #[test]
+    fn concat() {
+        assert!(Bytes::concat(Vec::<Bytes>::new()).as_bytes().is_empty());
+
+        let single = Bytes::from(b"hello".to_vec());
+        let ptr = single.as_bytes().as_ptr();
+        assert_eq!(Bytes::concat([single.clone()]).as_bytes().as_ptr(), ptr);
+        let padded = Bytes::concat([Bytes::default(), single, Bytes::default()]);
+        assert_eq!(padded.as_bytes().as_ptr(), ptr);
+
+        let joined = Bytes::concat([
+            Bytes::from_static(b"a"),
+            Bytes::default(),
+            Bytes::from_static(b"bc"),
+            Bytes::from_static(b""),
+            Bytes::from_static(b"def"),
+        ]);
+        assert_eq!(joined, Bytes::from_static(b"abcdef"));
+        assert_eq!(joined.as_bytes().len(), 6);
+    }
+
+    #[test]
+    fn static_conversions_borrow() {
+        static DATA: &[u8] = b"static data";
+        static TEXT: &str = "static text";
+        assert!(ptr::eq(Bytes::from_static(DATA).as_bytes(), DATA));
+        assert!(ptr::eq(Bytes::from(DATA).as_bytes(), DATA));
+        assert!(ptr::eq(Bytes::from(TEXT).as_bytes(), TEXT.as_bytes()));
+    }
+
+    #[test]
+    fn deterministic_hash() {
+        let bytes = Bytes::from_static(b"content");
+        assert_eq!(
+            hash_xxh3_hash64(&bytes),
+            hash_xxh3_hash64(&Bytes::from(b"content".to_vec()))
+        );
+        assert_eq!(hash_xxh3_hash64(&bytes), hash_xxh3_hash64(&b"content"[..]));
+        assert_ne!(
+            hash_xxh3_hash64(&bytes),
+            hash_xxh3_hash64(&Bytes::from_static(b"contend"))
+        );
+        assert_ne!(
+            hash_xxh3_hash64(&bytes),
+            hash_xxh3_hash64(&Bytes::from_static(b"conten"))
+        );
+        assert_ne!(
+            hash_xxh3_hash64(&Bytes::default()),
+            hash_xxh3_hash64(&Bytes::from_static(b"\0"))
+        );
+    }
+
+    #[test]
+    fn builder() {
+        let mut builder = BytesBuilder::new();
+        builder.put_u32(2);
+        let key = "id";
+        write!(builder, "{key}:").unwrap();
+        io::Write::write_all(&mut builder, b"\xff").unwrap();
+        assert_eq!(builder.len(), 8);
+        assert_eq!(
+            builder.freeze(),
+            Bytes::from_static(b"\x00\x00\x00\x02id:\xff")
+        );
+
+        assert!(builder.is_empty());
+        builder.push_str("again");
+        assert_eq!(builder.freeze(), Bytes::from_static(b"again"));
+    }
+
+    #[test]
+    fn builder_extend() {
+        let mut builder = BytesBuilder::new();
+        builder.extend([
+            Bytes::from_static(b"ab"),
+            Bytes::default(),
+            Bytes::from(b"cd".to_vec()),
+        ]);
+        builder.extend([&b"ef"[..], &b""[..]]);
+        builder.extend(b"gh".iter().copied());
+        assert_eq!(builder.len(), 8);
+        assert_eq!(builder.freeze(), Bytes::from_static(b"abcdefgh"));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Bytes::from_static(b"hello").to_string(), "hello");
+        assert_eq!(Bytes::from_static(b"a\xffb").to_string(), "a\u{fffd}b");
+
+        let bytes = Bytes::from_static(b"a\xc3\xa9\xff");
+        assert_eq!(format!("{bytes:.2}"), "aé…");
+        assert_eq!(format!("{bytes:.3}"), "aé\u{fffd}");
+
+        let wide = Bytes::from("€".repeat(10));
+        assert_eq!(format!("{wide:.9}"), format!("{}…", "€".repeat(9)));
+        assert_eq!(format!("{wide:.10}"), "€".repeat(10));
+        assert_eq!(format!("{wide:.1}"), "€…");
+        assert_eq!(format!("{wide:.0}"), "…");
+
+        let long = Bytes::from("x".repeat(2000));
+        assert_eq!(long.display_full().to_string(), "x".repeat(2000));
+    }
+
+    #[test]
+    fn to_string_is_capped() {
+        let long = Bytes::from("x".repeat(2000));
+        let string = long.to_string();
+        assert_eq!(string, format!("{}…", "x".repeat(1024)));
+        assert_ne!(string, long.to_string_lossy());
+        assert_eq!(long.to_string_lossy().len(), 2000);
+    }
+
+    #[test]
+    fn conversions_reuse_allocations() {
+        let vec = vec![1u8, 2, 3];
+        let ptr = vec.as_ptr();
+        assert_eq!(Bytes::from(vec).as_bytes().as_ptr(), ptr);
+
+        let string = "héllo".to_string();
+        let ptr = string.as_ptr();
+        let bytes = Bytes::from(string);
+        assert_eq!(bytes.as_bytes().as_ptr(), ptr);
+        let string = String::try_from(bytes).unwrap();
+        assert_eq!(string.as_ptr(), ptr);
+        assert_eq!(string, "héllo");
+
+        assert!(String::try_from(Bytes::from(vec![0xffu8])).is_err());
+        assert!(Bytes::default().as_bytes().is_empty());
+    }
+
+    #[test]
+    fn utf8() {
+        let bytes = Bytes::from_static("héllo".as_bytes());
+        assert_eq!(bytes.to_str().unwrap(), "héllo");
+        assert_eq!(bytes.to_string_lossy(), "héllo");
+
+        let invalid = Bytes::from_static(b"a\xc3(\xff");
+        let err = invalid.to_str().unwrap_err();
+        assert_eq!(err.valid_up_to(), 1);
+        assert_eq!(invalid.to_string_lossy(), "a\u{fffd}(\u{fffd}");
+        let err = invalid.clone().into_string().unwrap_err();
+        assert_eq!(err.utf8_error().valid_up_to(), 1);
+        assert_eq!(err.into_bytes(), invalid.as_bytes());
+    }
+
+    #[test]
+    fn into_string_reuses_unique_buffer() {
+        let string = "héllo".to_string();
+        let ptr = string.as_ptr();
+        let string = Bytes::from(string).into_string().unwrap();
+        assert_eq!(string.as_ptr(), ptr);
+
+        let shared = Bytes::from("héllo".to_string());
+        let copy = shared.clone().into_string().unwrap();
+        assert_eq!(copy, "héllo");
+        assert_eq!(shared.to_str().unwrap(), "héllo");
+    }
+
+    #[test]
+    fn debug_ascii() {
+        let bytes = Bytes::from_static(b"GET / HTTP/1.1\r\n");
+        assert_eq!(format!("{bytes:?}"), r#"b"GET / HTTP/1.1\r\n" (16B)"#);
+    }
+
+    #[test]
+    fn debug_non_ascii() {
+        let bytes = Bytes::from_static(b"\xff\x00\"a'\\");
+        assert_eq!(format!("{bytes:?}"), r#"b"\xff\x00\"a'\\" (6B)"#);
+    }
+
+    #[test]
+    fn debug_truncated() {
+        let bytes = Bytes::from("a".repeat(40));
+        assert_eq!(
+            format!("{bytes:?}"),
+            format!("b\"{}\"… (40B)", "a".repeat(32))
+        );
+        assert_eq!(
+            format!("{bytes:#?}"),
+            format!("b\"{}\" (40B)", "a".repeat(40))
+        );
+
+        let bytes = Bytes::from("a".repeat(2000));
+        assert_eq!(
+            format!("{bytes:?}"),
+            format!("b\"{}\"… (1.95KiB)", "a".repeat(32))
+        );
+    }
+
+    #[test]
+    fn serde_json_base64() {
+        let bytes = Bytes::from_static(b"hello");
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, r#""aGVsbG8=""#);
+        assert_eq!(serde_json::from_str::<Bytes>(&json).unwrap(), bytes);
+    }
+
+    #[test]
+    fn serde_json_legacy_array() {
+        let bytes: Bytes = serde_json::from_str("[104, 105, 0, 255]").unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"hi\x00\xff"));
+    }
+
+    #[test]
+    fn serde_json_invalid_base64() {
+        let err = serde_json::from_str::<Bytes>(r#""not base64!""#).unwrap_err();
+        assert!(
+            err.to_string().starts_with("invalid base64 bytes: "),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn serde_bincode_raw() {
+        let bytes = Bytes::from_static(b"\x00\xffab");
+        let encoded = bincode::serialize(&bytes).unwrap();
+        assert_eq!(encoded, b"\x04\x00\x00\x00\x00\x00\x00\x00\x00\xffab");
+        assert_eq!(bincode::deserialize::<Bytes>(&encoded).unwrap(), bytes);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod asset_graph;
+pub mod bytes;
 pub mod combined;
 pub mod conditional;
 pub mod headers;
@@ -16,11 +17,12 @@ pub mod wrapping_source;
 use std::{collections::BTreeSet, sync::Arc};
 
 use anyhow::Result;
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Serialize};
 use turbo_tasks::{trace::TraceRawVcs, Value};
 use turbo_tasks_fs::{rope::Rope, FileSystemPathVc};
 use turbopack_core::version::VersionedContentVc;
 
+pub use self::bytes::{Bytes, BytesBuilder};
 use self::{
     headers::Headers, issue_context::IssueContextContentSourceVc, query::Query,
     specificity::SpecificityVc,
@@ -253,37 +255,6 @@ impl Body {
     }
 }
 
-/// A wrapper around [hyper::body::Bytes] that implements [Serialize] and
-/// [Deserialize].
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub struct Bytes(hyper::body::Bytes);
-
-impl Bytes {
-    /// Returns the bytes as a slice.
-    pub fn as_bytes(&self) -> &[u8] {
-        self.0.as_ref()
-    }
-}
-
-impl From<hyper::body::Bytes> for Bytes {
-    fn from(bytes: hyper::body::Bytes) -> Self {
-        Self(bytes)
-    }
-}
-
-impl Serialize for Bytes {
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_bytes(self.0.as_ref())
-    }
-}
-
-impl<'de> Deserialize<'de> for Bytes {
-    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let bytes = Vec::<u8>::deserialize(deserializer)?;
-        Ok(Bytes(hyper::body::Bytes::from(bytes)))
-    }
-}
-
 impl Default for BodyVc {
     fn default() -> Self {
         Body::default().cell()